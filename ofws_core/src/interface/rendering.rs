@@ -4,6 +4,10 @@ use crate::rendering::tile::TileRenderer;
 
 pub type TextureId = usize;
 
+/// A handle for an off-screen render target created via
+/// [`Renderer::create_render_target`].
+pub type RenderTargetId = usize;
+
 /// A trait to load & init resources for rendering during initialization.
 pub trait Initialization {
     /// Loads a texture from a file and returns a `TextureId` as a handle.
@@ -44,8 +48,60 @@ pub trait Renderer {
 
     /// Gets a renderer for tiles.
     fn get_tile_renderer(&mut self, id: TextureId, tile_size: Size2d) -> TileRenderer;
+
+    /// Pushes a clip rectangle, intersected with the current one, onto the clip stack.
+    ///
+    /// `position` and `size` use [`Point`]'s bottom-left-origin, y-up convention,
+    /// like every other position in this trait; implementations are responsible
+    /// for converting to their graphics API's own convention if it differs.
+    ///
+    /// All drawing until the matching [`Renderer::pop_clip_rect`] is clamped to it.
+    /// Used to render scrollable lists or inset sub-windows without manually clamping
+    /// every position.
+    fn push_clip_rect(&mut self, position: Point, size: Point);
+
+    /// Pops the topmost clip rectangle, restoring the previous clip region.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clip stack is empty.
+    fn pop_clip_rect(&mut self);
+
+    /// Creates an off-screen render target of the given size in pixels.
+    ///
+    /// Used to layer effects like bloom, fog-of-war or a day/night tint over
+    /// the map, by rendering into the target and compositing it back with
+    /// [`Renderer::draw_target`]. The target gets its own projection sized to
+    /// match; it doesn't need to match the screen's size.
+    fn create_render_target(&mut self, size: Size2d) -> RenderTargetId;
+
+    /// Redirects all drawing to the render target `id` until the matching
+    /// [`Renderer::end_target`], clearing it to transparent first. The main
+    /// clip stack pushed via [`Renderer::push_clip_rect`] is main-screen
+    /// pixel space and does not apply while a target is bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a render target is already bound.
+    fn begin_target(&mut self, id: RenderTargetId);
+
+    /// Stops redirecting drawing to the currently bound render target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no render target is bound.
+    fn end_target(&mut self);
+
+    /// Composites a finished render target as a textured quad spanning
+    /// `position` to `position + size`.
+    ///
+    /// Targets with a lower `z` are drawn first, so higher `z` values appear
+    /// on top.
+    fn draw_target(&mut self, id: RenderTargetId, position: Point, size: Point, z: i32);
 }
 
+/// A 2d coordinate or extent. The origin is the bottom-left corner of the
+/// render target, with y increasing upward.
 pub type Point = (f32, f32);
 
 /// A trait that focuses on rendering colored polygons.
@@ -66,6 +122,28 @@ pub trait ColorRenderer {
 
     /// Renders an axis-aligned rectangle.
     fn render_rectangle(&mut self, position: Point, size: Point, color: Color);
+
+    /// Renders an axis-aligned rectangle filled with a linear gradient from
+    /// `color_a` to `color_b`. `angle` is the gradient direction in degrees,
+    /// measured counter-clockwise from the positive x-axis.
+    fn render_linear_gradient(
+        &mut self,
+        position: Point,
+        size: Point,
+        color_a: Color,
+        color_b: Color,
+        angle: f32,
+    );
+
+    /// Renders an axis-aligned rectangle filled with a radial gradient that
+    /// goes from `color_inner` at `center` to `color_outer` at `radius`.
+    fn render_radial_gradient(
+        &mut self,
+        center: Point,
+        radius: f32,
+        color_inner: Color,
+        color_outer: Color,
+    );
 }
 
 pub type TextureCoordinate = (f32, f32);
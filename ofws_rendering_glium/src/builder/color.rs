@@ -0,0 +1,194 @@
+use crate::color_space::{to_vertex_color, ColorSpace};
+use crate::renderer::get_other_corners;
+use ofws_core::data::color::Color;
+use ofws_core::interface::rendering::{ColorRenderer, Point};
+
+/// A vertex of a flat-shaded triangle drawn through `colored_program`.
+#[derive(Copy, Clone)]
+pub struct ColorVertex {
+    position: [f32; 2],
+    color: [f32; 4],
+}
+
+implement_vertex!(ColorVertex, position, color);
+
+/// A vertex of a linear-gradient triangle, carrying its own interpolated
+/// color instead of sharing one across the whole primitive.
+#[derive(Copy, Clone)]
+pub struct GradientVertex {
+    position: [f32; 2],
+    color: [f32; 4],
+}
+
+implement_vertex!(GradientVertex, position, color);
+
+/// A vertex of a radial-gradient quad. Just a position: the gradient's
+/// colors come from `RadialGradientDraw`'s uniforms instead of per-vertex
+/// attributes, since they're computed per-fragment from the distance to
+/// `center`.
+#[derive(Copy, Clone)]
+pub struct RadialGradientVertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(RadialGradientVertex, position);
+
+/// One radial gradient queued by [`ColorBuilder::render_radial_gradient`],
+/// drawn as its own `colored.gradient_radial` draw call since (unlike linear
+/// gradients) it can't be batched into a shared per-vertex-color stream.
+pub struct RadialGradientDraw {
+    pub vertices: [RadialGradientVertex; 6],
+    pub center: [f32; 2],
+    pub radius: f32,
+    pub color_inner: [f32; 4],
+    pub color_outer: [f32; 4],
+}
+
+/// Accumulates the vertices/draws `GliumRenderer` uploads for the colored and
+/// gradient render passes each frame.
+pub struct ColorBuilder {
+    color_space: ColorSpace,
+    pub vertices: Vec<ColorVertex>,
+    pub gradient_vertices: Vec<GradientVertex>,
+    pub radial_gradients: Vec<RadialGradientDraw>,
+}
+
+impl ColorBuilder {
+    pub fn new(color_space: ColorSpace) -> ColorBuilder {
+        ColorBuilder {
+            color_space,
+            vertices: Vec::new(),
+            gradient_vertices: Vec::new(),
+            radial_gradients: Vec::new(),
+        }
+    }
+
+    /// Switches between legacy linear-byte and gamma-correct sRGB color handling.
+    pub fn set_color_space(&mut self, color_space: ColorSpace) {
+        self.color_space = color_space;
+    }
+
+    fn to_vertex_color(&self, color: Color) -> [f32; 4] {
+        to_vertex_color(color, self.color_space)
+    }
+}
+
+impl ColorRenderer for ColorBuilder {
+    fn render_triangle(&mut self, a: Point, b: Point, c: Point, color: Color) {
+        let color = self.to_vertex_color(color);
+        self.vertices.push(ColorVertex {
+            position: [a.0, a.1],
+            color,
+        });
+        self.vertices.push(ColorVertex {
+            position: [b.0, b.1],
+            color,
+        });
+        self.vertices.push(ColorVertex {
+            position: [c.0, c.1],
+            color,
+        });
+    }
+
+    fn render_rectangle(&mut self, position: Point, size: Point, color: Color) {
+        let color = self.to_vertex_color(color);
+        let [c10, c01, c11] = get_other_corners(position, size);
+        let vertex = |point: Point| ColorVertex {
+            position: [point.0, point.1],
+            color,
+        };
+
+        self.vertices.extend_from_slice(&[
+            vertex(position),
+            vertex(c10),
+            vertex(c01),
+            vertex(c10),
+            vertex(c11),
+            vertex(c01),
+        ]);
+    }
+
+    fn render_linear_gradient(
+        &mut self,
+        position: Point,
+        size: Point,
+        color_a: Color,
+        color_b: Color,
+        angle: f32,
+    ) {
+        let color_a = self.to_vertex_color(color_a);
+        let color_b = self.to_vertex_color(color_b);
+        let [c10, c01, c11] = get_other_corners(position, size);
+        let corners = [position, c10, c01, c11];
+
+        // Interpolates each corner's color by its projection onto the
+        // gradient axis, so the gradient runs along `angle` regardless of
+        // the rectangle's own orientation.
+        let radians = angle.to_radians();
+        let direction = (radians.cos(), radians.sin());
+        let project = |point: Point| point.0 * direction.0 + point.1 * direction.1;
+
+        let min_t = corners
+            .iter()
+            .map(|&point| project(point))
+            .fold(f32::INFINITY, f32::min);
+        let max_t = corners
+            .iter()
+            .map(|&point| project(point))
+            .fold(f32::NEG_INFINITY, f32::max);
+        let span = (max_t - min_t).max(f32::EPSILON);
+
+        let vertex = |point: Point| {
+            let t = ((project(point) - min_t) / span).clamp(0.0, 1.0);
+            let color = [
+                color_a[0] + (color_b[0] - color_a[0]) * t,
+                color_a[1] + (color_b[1] - color_a[1]) * t,
+                color_a[2] + (color_b[2] - color_a[2]) * t,
+                color_a[3] + (color_b[3] - color_a[3]) * t,
+            ];
+            GradientVertex {
+                position: [point.0, point.1],
+                color,
+            }
+        };
+
+        self.gradient_vertices.extend_from_slice(&[
+            vertex(position),
+            vertex(c10),
+            vertex(c01),
+            vertex(c10),
+            vertex(c11),
+            vertex(c01),
+        ]);
+    }
+
+    fn render_radial_gradient(
+        &mut self,
+        center: Point,
+        radius: f32,
+        color_inner: Color,
+        color_outer: Color,
+    ) {
+        let position = (center.0 - radius, center.1 - radius);
+        let size = (radius * 2.0, radius * 2.0);
+        let [c10, c01, c11] = get_other_corners(position, size);
+        let vertex = |point: Point| RadialGradientVertex {
+            position: [point.0, point.1],
+        };
+
+        self.radial_gradients.push(RadialGradientDraw {
+            vertices: [
+                vertex(position),
+                vertex(c10),
+                vertex(c01),
+                vertex(c10),
+                vertex(c11),
+                vertex(c01),
+            ],
+            center: [center.0, center.1],
+            radius,
+            color_inner: self.to_vertex_color(color_inner),
+            color_outer: self.to_vertex_color(color_outer),
+        });
+    }
+}
@@ -0,0 +1,120 @@
+use crate::color_space::{to_vertex_color, ColorSpace};
+use ofws_core::data::color::Color;
+use ofws_core::interface::rendering::{AsciiRenderer, Point, TextureCoordinate, TextureRenderer};
+
+/// One instanced glyph/tile quad. The GPU expands this into 4 vertices from
+/// the static unit quad in `renderer::QUAD_VERTICES`, positioning and
+/// texturing them from these per-instance attributes instead of uploading
+/// 6 raw vertices per tile.
+#[derive(Copy, Clone)]
+pub struct TileInstance {
+    tile_position: [f32; 2],
+    tile_size: [f32; 2],
+    tex_coords_min: [f32; 2],
+    tex_coords_max: [f32; 2],
+    fg_color: [f32; 4],
+    bg_color: [f32; 4],
+}
+
+implement_vertex!(
+    TileInstance,
+    tile_position,
+    tile_size,
+    tex_coords_min,
+    tex_coords_max,
+    fg_color,
+    bg_color
+);
+
+/// Accumulates one [`TileInstance`] per glyph or textured rectangle drawn
+/// this frame, for `GliumRenderer` to upload as a single per-instance buffer.
+pub struct TextureBuilder {
+    /// Width, in glyphs, of the bitmap font atlas backing this texture. Used
+    /// by [`AsciiRenderer::render_u8`] to turn an ascii byte into a texture
+    /// cell.
+    chars_per_row: u32,
+    color_space: ColorSpace,
+    pub instances: Vec<TileInstance>,
+}
+
+impl TextureBuilder {
+    pub fn new(chars_per_row: u32, color_space: ColorSpace) -> TextureBuilder {
+        TextureBuilder {
+            chars_per_row,
+            color_space,
+            instances: Vec::new(),
+        }
+    }
+
+    /// Switches between legacy linear-byte and gamma-correct sRGB color handling.
+    pub fn set_color_space(&mut self, color_space: ColorSpace) {
+        self.color_space = color_space;
+    }
+
+    fn to_vertex_color(&self, color: Color) -> [f32; 4] {
+        to_vertex_color(color, self.color_space)
+    }
+
+    /// Maps an ascii byte to its cell in the bitmap font atlas.
+    fn char_tex_coords(&self, ascii: u8) -> (TextureCoordinate, TextureCoordinate) {
+        let cell = 1.0 / self.chars_per_row as f32;
+        let column = (ascii as u32 % self.chars_per_row) as f32;
+        let row = (ascii as u32 / self.chars_per_row) as f32;
+        ((column * cell, row * cell), (cell, cell))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        position: Point,
+        size: Point,
+        tc: TextureCoordinate,
+        tc_size: TextureCoordinate,
+        fg_color: [f32; 4],
+        bg_color: [f32; 4],
+    ) {
+        self.instances.push(TileInstance {
+            tile_position: [position.0, position.1],
+            tile_size: [size.0, size.1],
+            tex_coords_min: [tc.0, tc.1],
+            tex_coords_max: [tc.0 + tc_size.0, tc.1 + tc_size.1],
+            fg_color,
+            bg_color,
+        });
+    }
+}
+
+impl TextureRenderer for TextureBuilder {
+    fn render_rectangle(
+        &mut self,
+        position: Point,
+        size: Point,
+        tc: TextureCoordinate,
+        tc_size: TextureCoordinate,
+        color: Color,
+    ) {
+        let color = self.to_vertex_color(color);
+        self.push(position, size, tc, tc_size, color, color);
+    }
+}
+
+impl AsciiRenderer for TextureBuilder {
+    fn render_text(&mut self, position: Point, size: Point, string: &str, color: Color) {
+        for (index, character) in string.chars().enumerate() {
+            let char_position = (position.0 + index as f32 * size.0, position.1);
+            self.render_char(char_position, size, character, color);
+        }
+    }
+
+    fn render_char(&mut self, position: Point, size: Point, character: char, color: Color) {
+        if character.is_ascii() {
+            self.render_u8(position, size, character as u8, color);
+        }
+    }
+
+    fn render_u8(&mut self, position: Point, size: Point, ascii: u8, color: Color) {
+        let (tc, tc_size) = self.char_tex_coords(ascii);
+        let fg_color = self.to_vertex_color(color);
+        self.push(position, size, tc, tc_size, fg_color, [0.0, 0.0, 0.0, 0.0]);
+    }
+}
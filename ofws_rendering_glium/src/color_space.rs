@@ -0,0 +1,49 @@
+use ofws_core::data::color::Color;
+
+/// Selects how `Color` byte values are interpreted before they reach the GPU.
+///
+/// Shared by `GliumRenderer` and the `ColorBuilder`/`TextureBuilder` vertex
+/// builders, since all three convert a `Color` into `[f32; 4]` at some point
+/// and must agree on how.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// Treats bytes as linear values divided by 255. Matches the renderer's
+    /// historical behavior and keeps old screenshots reproducible.
+    Linear,
+    /// Converts bytes through the sRGB transfer function before upload, which
+    /// is what correctly blended, alpha-blended textured output needs.
+    Srgb,
+}
+
+/// Converts a single sRGB-encoded color channel to linear space.
+fn srgb_to_linear(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a byte color channel to `[0, 1]` according to `color_space`.
+pub fn normalize_channel(value: u8, color_space: ColorSpace) -> f32 {
+    match color_space {
+        ColorSpace::Linear => value as f32 / 255.0,
+        ColorSpace::Srgb => srgb_to_linear(value),
+    }
+}
+
+/// Converts a `Color` into a vertex color according to `color_space`.
+///
+/// Only r/g/b go through the sRGB transfer function: alpha is a linear
+/// coverage value, not a gamma-encoded light intensity, and converting it
+/// the same way would make every partially transparent draw far more
+/// transparent than its byte value asked for.
+pub fn to_vertex_color(color: Color, color_space: ColorSpace) -> [f32; 4] {
+    [
+        normalize_channel(color.r(), color_space),
+        normalize_channel(color.g(), color_space),
+        normalize_channel(color.b(), color_space),
+        color.a() as f32 / 255.0,
+    ]
+}
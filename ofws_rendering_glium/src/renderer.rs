@@ -1,21 +1,159 @@
 use crate::builder::color::ColorBuilder;
 use crate::builder::texture::TextureBuilder;
+use crate::color_space::normalize_channel;
 use crate::shader::load_program;
 use cgmath::ortho;
-use glium::{Program, Surface};
+use glium::index::PrimitiveType;
+use glium::{IndexBuffer, Program, Surface, VertexBuffer};
 use ofws_core::data::color::Color;
 use ofws_core::data::math::size2d::Size2d;
 use ofws_core::interface::rendering::{
-    AsciiRenderer, ColorRenderer, Renderer, TextureId, TextureRenderer,
+    AsciiRenderer, ColorRenderer, Point, RenderTargetId, Renderer, TextureId, TextureRenderer,
 };
 use ofws_core::rendering::tile::{calculate_tiles, TileRenderer};
 
+pub use crate::color_space::ColorSpace;
+
 const INDICES: glium::index::NoIndices =
     glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
 
+/// The 4 corners of a single unit quad, shared by every instanced tile.
+#[derive(Copy, Clone)]
+struct QuadVertex {
+    corner: [f32; 2],
+}
+
+implement_vertex!(QuadVertex, corner);
+
+const QUAD_VERTICES: [QuadVertex; 4] = [
+    QuadVertex { corner: [0.0, 0.0] },
+    QuadVertex { corner: [1.0, 0.0] },
+    QuadVertex { corner: [0.0, 1.0] },
+    QuadVertex { corner: [1.0, 1.0] },
+];
+const QUAD_INDICES: [u16; 6] = [0, 1, 2, 1, 3, 2];
+
+/// Builds the orthographic projection for a surface of `size` pixels, with
+/// the origin at its bottom-left corner and y increasing upward. Shared by the main screen and every
+/// render target, so each gets content sized to its own pixel dimensions.
+fn screen_matrix(size: Size2d) -> cgmath::Matrix4<f32> {
+    ortho(
+        0.0,
+        size.width() as f32,
+        0.0,
+        size.height() as f32,
+        -1.0,
+        1.0,
+    )
+}
+
+/// Builds the glutin context config the display passed to [`GliumRenderer::new`]
+/// should be created with. Requests an sRGB-capable framebuffer for
+/// [`ColorSpace::Srgb`], so the GPU re-encodes the builders' linear output on
+/// write instead of leaving the conversion one-sided.
+pub fn context_builder(
+    color_space: ColorSpace,
+) -> glium::glutin::ContextBuilder<'static, glium::glutin::NotCurrent> {
+    glium::glutin::ContextBuilder::new().with_srgb(color_space == ColorSpace::Srgb)
+}
+
+/// Intersects two axis-aligned scissor rects, clamping to an empty rect if they
+/// don't overlap at all.
+fn intersect_rects(a: glium::Rect, b: glium::Rect) -> glium::Rect {
+    let left = a.left.max(b.left);
+    let bottom = a.bottom.max(b.bottom);
+    let right = (a.left + a.width).min(b.left + b.width);
+    let top = (a.bottom + a.height).min(b.bottom + b.height);
+
+    glium::Rect {
+        left,
+        bottom,
+        width: right.saturating_sub(left),
+        height: top.saturating_sub(bottom),
+    }
+}
+
+/// How many buffers to rotate through per vertex stream, so writing into the
+/// buffer for frame N doesn't stall on the GPU possibly still reading frame N-1
+/// (classic buffer orphaning).
+const BUFFER_RING_SIZE: usize = 3;
+
+/// A small ring of growable vertex buffers reused across frames instead of
+/// allocating a fresh `glium::VertexBuffer` every `finish()`.
+struct VertexRing<T: Copy + glium::Vertex> {
+    buffers: Vec<glium::VertexBuffer<T>>,
+    next: usize,
+}
+
+impl<T: Copy + glium::Vertex> VertexRing<T> {
+    fn new(display: &glium::Display, capacity: usize) -> VertexRing<T> {
+        let buffers = (0..BUFFER_RING_SIZE)
+            .map(|_| glium::VertexBuffer::empty_dynamic(display, capacity).unwrap())
+            .collect();
+        VertexRing { buffers, next: 0 }
+    }
+
+    /// Rotates to the ring's next buffer. Called once per frame (from `start()`),
+    /// not per upload, so every flush within the same frame keeps reusing this
+    /// frame's buffer instead of cycling through buffers the GPU may still be
+    /// reading from a previous frame.
+    fn advance(&mut self) {
+        self.next = (self.next + 1) % self.buffers.len();
+    }
+
+    /// Uploads `data` into the ring's current buffer, reallocating it first if it
+    /// has outgrown the buffer's capacity, and returns a slice sized to `data` for drawing.
+    fn upload(
+        &mut self,
+        display: &glium::Display,
+        data: &[T],
+    ) -> glium::vertex::VertexBufferSlice<T> {
+        let index = self.next;
+
+        if data.len() > self.buffers[index].len() {
+            self.buffers[index] = glium::VertexBuffer::empty_dynamic(display, data.len()).unwrap();
+        }
+
+        let slice = self.buffers[index].slice(0..data.len()).unwrap();
+        slice.write(data);
+        slice
+    }
+}
+
 struct TextureData {
     texture: glium::texture::Texture2d,
     builder: TextureBuilder,
+    instance_ring: VertexRing<crate::builder::texture::TileInstance>,
+}
+
+/// A vertex of a full-texture quad used to composite a finished render target.
+#[derive(Copy, Clone)]
+struct CompositeVertex {
+    position: [f32; 2],
+    tex_coord: [f32; 2],
+}
+
+implement_vertex!(CompositeVertex, position, tex_coord);
+
+struct RenderTargetData {
+    texture: glium::texture::Texture2d,
+    /// The target's own orthographic projection, built from its pixel size
+    /// rather than the main screen's, so content drawn into a target whose
+    /// size differs from the screen isn't scaled/cropped by the wrong matrix.
+    matrix: cgmath::Matrix4<f32>,
+}
+
+/// A finished render target queued to be composited back into the main frame
+/// during `finish()`, ordered back-to-front by `z`.
+struct PendingComposite {
+    id: RenderTargetId,
+    position: Point,
+    size: Point,
+    z: i32,
+    /// The clip rect active when `draw_target` queued this composite, since
+    /// composites are drawn in their own pass after the clip stack that was
+    /// in effect has already been popped.
+    scissor: Option<glium::Rect>,
 }
 
 pub struct GliumRenderer {
@@ -23,97 +161,468 @@ pub struct GliumRenderer {
     display: glium::Display,
     target: Option<glium::Frame>,
     color_builder: ColorBuilder,
+    color_ring: VertexRing<crate::builder::color::ColorVertex>,
     colored_program: Program,
     textured_program: Program,
+    composite_program: Program,
+    gradient_program: Program,
+    radial_gradient_program: Program,
+    gradient_ring: VertexRing<crate::builder::color::GradientVertex>,
+    radial_gradient_ring: VertexRing<crate::builder::color::RadialGradientVertex>,
     texture_data: Vec<TextureData>,
     matrix: cgmath::Matrix4<f32>,
+    quad_vertex_buffer: VertexBuffer<QuadVertex>,
+    quad_index_buffer: IndexBuffer<u16>,
+    clip_stack: Vec<glium::Rect>,
+    color_space: ColorSpace,
+    render_targets: Vec<RenderTargetData>,
+    active_target: Option<RenderTargetId>,
+    pending_composites: Vec<PendingComposite>,
+    composite_ring: VertexRing<CompositeVertex>,
 }
 
 impl GliumRenderer {
+    /// Creates a new `GliumRenderer`.
+    ///
+    /// `color_space` selects whether `Color` bytes are uploaded as-is
+    /// ([`ColorSpace::Linear`], the legacy behavior) or converted through the
+    /// sRGB transfer function first ([`ColorSpace::Srgb`], which blends
+    /// correctly but changes existing screenshots). It is forwarded to the
+    /// `ColorBuilder`/`TextureBuilder`, which apply it to every vertex color
+    /// they emit (rectangles, gradients, glyphs, tiles), not just the
+    /// `start()` clear color below. `display` should be built from a context
+    /// created via [`context_builder`] with the same `color_space`, so the
+    /// framebuffer itself is sRGB-capable when using [`ColorSpace::Srgb`].
     pub fn new(
         display: glium::Display,
         textures: Vec<glium::texture::Texture2d>,
         size: Size2d,
+        color_space: ColorSpace,
     ) -> GliumRenderer {
         let colored_program = load_program(&display, "colored.vertex", "colored.fragment");
         let textured_program = load_program(&display, "textured.vertex", "textured.fragment");
-
-        let matrix: cgmath::Matrix4<f32> = ortho(
-            0.0,
-            size.width() as f32,
-            0.0,
-            size.height() as f32,
-            -1.0,
-            1.0,
+        let composite_program = load_program(&display, "composite.vertex", "composite.fragment");
+        let gradient_program = load_program(
+            &display,
+            "colored.gradient.vertex",
+            "colored.gradient.fragment",
         );
+        let radial_gradient_program = load_program(
+            &display,
+            "colored.gradient_radial.vertex",
+            "colored.gradient_radial.fragment",
+        );
+
+        let matrix = screen_matrix(size);
 
         let texture_data = textures
             .into_iter()
             .map(|texture| TextureData {
                 texture,
-                builder: TextureBuilder::new(16),
+                builder: TextureBuilder::new(16, color_space),
+                instance_ring: VertexRing::new(&display, 1024),
             })
             .collect();
 
+        let quad_vertex_buffer = VertexBuffer::new(&display, &QUAD_VERTICES).unwrap();
+        let quad_index_buffer =
+            IndexBuffer::new(&display, PrimitiveType::TrianglesList, &QUAD_INDICES).unwrap();
+
         GliumRenderer {
             size,
             display,
             target: None,
-            color_builder: ColorBuilder::default(),
+            color_builder: ColorBuilder::new(color_space),
+            color_ring: VertexRing::new(&display, 1024),
             colored_program,
             textured_program,
+            composite_program,
+            gradient_program,
+            radial_gradient_program,
+            gradient_ring: VertexRing::new(&display, 256),
+            radial_gradient_ring: VertexRing::new(&display, 6),
             texture_data,
             matrix,
+            quad_vertex_buffer,
+            quad_index_buffer,
+            clip_stack: Vec::new(),
+            color_space,
+            render_targets: Vec::new(),
+            active_target: None,
+            pending_composites: Vec::new(),
+            composite_ring: VertexRing::new(&display, 6),
         }
     }
 
-    fn render_colored_triangles(&mut self) {
-        let target = self.target.as_mut().unwrap();
-        let vertex_buffer =
-            glium::VertexBuffer::new(&self.display, &self.color_builder.vertices).unwrap();
+    /// Switches between legacy linear-byte and gamma-correct sRGB color handling.
+    ///
+    /// Propagates to the color/texture builders too, since they are the ones
+    /// that convert `Color` into the `[f32; 4]` vertex colors actually drawn,
+    /// not just the `start()` clear color.
+    pub fn set_color_space(&mut self, color_space: ColorSpace) {
+        self.color_space = color_space;
+        self.color_builder.set_color_space(color_space);
+        self.texture_data
+            .iter_mut()
+            .for_each(|data| data.builder.set_color_space(color_space));
+    }
 
+    /// The scissor rect for the current clip region, or `None` if nothing is clipped.
+    fn current_scissor(&self) -> Option<glium::Rect> {
+        self.clip_stack.last().copied()
+    }
+
+    /// Draws `color_builder.vertices` onto `surface`: the default frame, or the
+    /// framebuffer of the active render target.
+    fn draw_colored_triangles<S: glium::Surface>(
+        surface: &mut S,
+        display: &glium::Display,
+        ring: &mut VertexRing<crate::builder::color::ColorVertex>,
+        vertices: &mut Vec<crate::builder::color::ColorVertex>,
+        program: &Program,
+        matrix: cgmath::Matrix4<f32>,
+        scissor: Option<glium::Rect>,
+    ) {
+        if vertices.is_empty() {
+            return;
+        }
+
+        let vertex_buffer = ring.upload(display, vertices);
         let uniforms = uniform! {
-            matrix: Into::<[[f32; 4]; 4]>::into(self.matrix)
+            matrix: Into::<[[f32; 4]; 4]>::into(matrix)
+        };
+        let draw_parameters = glium::draw_parameters::DrawParameters {
+            scissor,
+            ..Default::default()
         };
 
-        target
+        surface
             .draw(
-                &vertex_buffer,
+                vertex_buffer,
                 &INDICES,
-                &self.colored_program,
+                program,
                 &uniforms,
-                &Default::default(),
+                &draw_parameters,
             )
             .unwrap();
+        vertices.clear();
     }
 
-    fn render_textured_triangles(&mut self) {
-        let target = self.target.as_mut().unwrap();
+    fn render_colored_triangles(&mut self) {
+        match self.active_target {
+            Some(id) => {
+                let matrix = self.render_targets[id].matrix;
+                let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(
+                    &self.display,
+                    &self.render_targets[id].texture,
+                )
+                .unwrap();
+                // The main clip stack is in main-screen pixel space, which is
+                // meaningless for a target of a different size, so targets
+                // are never clipped by it.
+                Self::draw_colored_triangles(
+                    &mut framebuffer,
+                    &self.display,
+                    &mut self.color_ring,
+                    &mut self.color_builder.vertices,
+                    &self.colored_program,
+                    matrix,
+                    None,
+                );
+            }
+            None => {
+                let scissor = self.current_scissor();
+                let matrix = self.matrix;
+                let mut target = self
+                    .target
+                    .take()
+                    .expect("start() must be called before drawing");
+                Self::draw_colored_triangles(
+                    &mut target,
+                    &self.display,
+                    &mut self.color_ring,
+                    &mut self.color_builder.vertices,
+                    &self.colored_program,
+                    matrix,
+                    scissor,
+                );
+                self.target = Some(target);
+            }
+        }
+    }
 
+    // Draws one instanced quad per tile instead of six raw vertices, cutting vertex
+    // bandwidth roughly 6x for large ascii/tile maps.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_textured_triangles<S: glium::Surface>(
+        surface: &mut S,
+        display: &glium::Display,
+        texture_data: &mut [TextureData],
+        quad_vertex_buffer: &VertexBuffer<QuadVertex>,
+        quad_index_buffer: &IndexBuffer<u16>,
+        program: &Program,
+        matrix: cgmath::Matrix4<f32>,
+        scissor: Option<glium::Rect>,
+    ) {
         let draw_parameters = glium::draw_parameters::DrawParameters {
             blend: glium::draw_parameters::Blend::alpha_blending(),
+            scissor,
             ..glium::draw_parameters::DrawParameters::default()
         };
 
-        for data in &self.texture_data {
-            let vertex_buffer =
-                glium::VertexBuffer::new(&self.display, &data.builder.vertices).unwrap();
+        for data in texture_data {
+            if data.builder.instances.is_empty() {
+                continue;
+            }
+
+            let instance_buffer = data.instance_ring.upload(display, &data.builder.instances);
 
             let uniforms = uniform! {
-                matrix: Into::<[[f32; 4]; 4]>::into(self.matrix),
+                matrix: Into::<[[f32; 4]; 4]>::into(matrix),
                 tex: &data.texture,
             };
 
+            surface
+                .draw(
+                    (quad_vertex_buffer, instance_buffer.per_instance().unwrap()),
+                    quad_index_buffer,
+                    program,
+                    &uniforms,
+                    &draw_parameters,
+                )
+                .unwrap();
+
+            data.builder.instances.clear();
+        }
+    }
+
+    fn render_textured_triangles(&mut self) {
+        match self.active_target {
+            Some(id) => {
+                let matrix = self.render_targets[id].matrix;
+                let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(
+                    &self.display,
+                    &self.render_targets[id].texture,
+                )
+                .unwrap();
+                Self::draw_textured_triangles(
+                    &mut framebuffer,
+                    &self.display,
+                    &mut self.texture_data,
+                    &self.quad_vertex_buffer,
+                    &self.quad_index_buffer,
+                    &self.textured_program,
+                    matrix,
+                    None,
+                );
+            }
+            None => {
+                let scissor = self.current_scissor();
+                let matrix = self.matrix;
+                let mut target = self
+                    .target
+                    .take()
+                    .expect("start() must be called before drawing");
+                Self::draw_textured_triangles(
+                    &mut target,
+                    &self.display,
+                    &mut self.texture_data,
+                    &self.quad_vertex_buffer,
+                    &self.quad_index_buffer,
+                    &self.textured_program,
+                    matrix,
+                    scissor,
+                );
+                self.target = Some(target);
+            }
+        }
+    }
+
+    // Third render pass for gradient fills: linear gradients are batched as
+    // per-vertex interpolated color triangles, radial gradients are drawn one
+    // quad at a time since each carries its own center/radius uniforms.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_gradient_triangles<S: glium::Surface>(
+        surface: &mut S,
+        display: &glium::Display,
+        ring: &mut VertexRing<crate::builder::color::GradientVertex>,
+        gradient_vertices: &mut Vec<crate::builder::color::GradientVertex>,
+        radial_ring: &mut VertexRing<crate::builder::color::RadialGradientVertex>,
+        radial_gradients: &mut Vec<crate::builder::color::RadialGradientDraw>,
+        gradient_program: &Program,
+        radial_gradient_program: &Program,
+        matrix: cgmath::Matrix4<f32>,
+        scissor: Option<glium::Rect>,
+    ) {
+        let draw_parameters = glium::draw_parameters::DrawParameters {
+            blend: glium::draw_parameters::Blend::alpha_blending(),
+            scissor,
+            ..Default::default()
+        };
+
+        if !gradient_vertices.is_empty() {
+            let vertex_buffer = ring.upload(display, gradient_vertices);
+            let uniforms = uniform! {
+                matrix: Into::<[[f32; 4]; 4]>::into(matrix)
+            };
+
+            surface
+                .draw(
+                    vertex_buffer,
+                    &INDICES,
+                    gradient_program,
+                    &uniforms,
+                    &draw_parameters,
+                )
+                .unwrap();
+
+            gradient_vertices.clear();
+        }
+
+        for gradient in radial_gradients.drain(..) {
+            let vertex_buffer = radial_ring.upload(display, &gradient.vertices);
+            let uniforms = uniform! {
+                matrix: Into::<[[f32; 4]; 4]>::into(matrix),
+                center: gradient.center,
+                radius: gradient.radius,
+                color_inner: gradient.color_inner,
+                color_outer: gradient.color_outer,
+            };
+
+            surface
+                .draw(
+                    vertex_buffer,
+                    &INDICES,
+                    radial_gradient_program,
+                    &uniforms,
+                    &draw_parameters,
+                )
+                .unwrap();
+        }
+    }
+
+    fn render_gradient_triangles(&mut self) {
+        match self.active_target {
+            Some(id) => {
+                let matrix = self.render_targets[id].matrix;
+                let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(
+                    &self.display,
+                    &self.render_targets[id].texture,
+                )
+                .unwrap();
+                Self::draw_gradient_triangles(
+                    &mut framebuffer,
+                    &self.display,
+                    &mut self.gradient_ring,
+                    &mut self.color_builder.gradient_vertices,
+                    &mut self.radial_gradient_ring,
+                    &mut self.color_builder.radial_gradients,
+                    &self.gradient_program,
+                    &self.radial_gradient_program,
+                    matrix,
+                    None,
+                );
+            }
+            None => {
+                let scissor = self.current_scissor();
+                let matrix = self.matrix;
+                let mut target = self
+                    .target
+                    .take()
+                    .expect("start() must be called before drawing");
+                Self::draw_gradient_triangles(
+                    &mut target,
+                    &self.display,
+                    &mut self.gradient_ring,
+                    &mut self.color_builder.gradient_vertices,
+                    &mut self.radial_gradient_ring,
+                    &mut self.color_builder.radial_gradients,
+                    &self.gradient_program,
+                    &self.radial_gradient_program,
+                    matrix,
+                    scissor,
+                );
+                self.target = Some(target);
+            }
+        }
+    }
+
+    // Draws every finished render target queued by `draw_target` as a textured
+    // quad, back-to-front by `z`, compositing them into the default frame.
+    // Each composite reapplies the clip rect recorded at its `draw_target`
+    // call, since by now the clip stack that was active then has been popped.
+    fn render_composites(&mut self) {
+        if self.pending_composites.is_empty() {
+            return;
+        }
+
+        self.pending_composites.sort_by_key(|composite| composite.z);
+
+        let mut target = self
+            .target
+            .take()
+            .expect("start() must be called before drawing");
+
+        for composite in self.pending_composites.drain(..) {
+            let [c10, c01, c11] = get_other_corners(composite.position, composite.size);
+            let vertices = [
+                CompositeVertex {
+                    position: [composite.position.0, composite.position.1],
+                    tex_coord: [0.0, 0.0],
+                },
+                CompositeVertex {
+                    position: [c10.0, c10.1],
+                    tex_coord: [1.0, 0.0],
+                },
+                CompositeVertex {
+                    position: [c01.0, c01.1],
+                    tex_coord: [0.0, 1.0],
+                },
+                CompositeVertex {
+                    position: [c10.0, c10.1],
+                    tex_coord: [1.0, 0.0],
+                },
+                CompositeVertex {
+                    position: [c11.0, c11.1],
+                    tex_coord: [1.0, 1.0],
+                },
+                CompositeVertex {
+                    position: [c01.0, c01.1],
+                    tex_coord: [0.0, 1.0],
+                },
+            ];
+
+            let vertex_buffer = self.composite_ring.upload(&self.display, &vertices);
+            let uniforms = uniform! {
+                matrix: Into::<[[f32; 4]; 4]>::into(self.matrix),
+                tex: &self.render_targets[composite.id].texture,
+            };
+            let draw_parameters = glium::draw_parameters::DrawParameters {
+                blend: glium::draw_parameters::Blend::alpha_blending(),
+                scissor: composite.scissor,
+                ..Default::default()
+            };
+
             target
                 .draw(
-                    &vertex_buffer,
+                    vertex_buffer,
                     &INDICES,
-                    &self.textured_program,
+                    &self.composite_program,
                     &uniforms,
                     &draw_parameters,
                 )
                 .unwrap();
         }
+
+        self.target = Some(target);
+    }
+
+    /// Flushes everything accumulated for the current clip region before the clip
+    /// stack changes, so a batch never straddles two different scissor rects.
+    fn flush_clip_batch(&mut self) {
+        self.render_colored_triangles();
+        self.render_textured_triangles();
+        self.render_gradient_triangles();
     }
 }
 
@@ -125,22 +634,35 @@ impl Renderer for GliumRenderer {
     fn start(&mut self, color: Color) {
         let mut target = self.display.draw();
         target.clear_color(
-            color.r() as f32 / 255.0,
-            color.g() as f32 / 255.0,
-            color.b() as f32 / 255.0,
+            normalize_channel(color.r(), self.color_space),
+            normalize_channel(color.g(), self.color_space),
+            normalize_channel(color.b(), self.color_space),
             1.0,
         );
         self.target = Some(target);
 
         self.color_builder.vertices.clear();
-        self.texture_data
-            .iter_mut()
-            .for_each(|x| x.builder.vertices.clear());
+        self.color_builder.gradient_vertices.clear();
+        self.color_builder.radial_gradients.clear();
+        self.texture_data.iter_mut().for_each(|x| {
+            x.builder.instances.clear();
+            x.instance_ring.advance();
+        });
+        self.clip_stack.clear();
+        self.active_target = None;
+        self.pending_composites.clear();
+
+        self.color_ring.advance();
+        self.gradient_ring.advance();
+        self.radial_gradient_ring.advance();
+        self.composite_ring.advance();
     }
 
     fn finish(&mut self) {
         self.render_colored_triangles();
         self.render_textured_triangles();
+        self.render_gradient_triangles();
+        self.render_composites();
 
         if let Some(target) = self.target.take() {
             target.finish().unwrap();
@@ -172,6 +694,80 @@ impl Renderer for GliumRenderer {
         let tiles = calculate_tiles(self.size, tile_size);
         TileRenderer::new(tiles, tile_size, &mut self.texture_data[id].builder)
     }
+
+    fn push_clip_rect(&mut self, position: Point, size: Point) {
+        self.flush_clip_batch();
+
+        // `Point` and `glium::Rect.bottom` both measure y upward from the
+        // bottom of the surface (see `screen_matrix`), so the rect maps
+        // across directly; negative values are clamped to zero.
+        let rect = glium::Rect {
+            left: position.0.max(0.0) as u32,
+            bottom: position.1.max(0.0) as u32,
+            width: size.0.max(0.0) as u32,
+            height: size.1.max(0.0) as u32,
+        };
+        let clipped = match self.clip_stack.last() {
+            Some(&parent) => intersect_rects(parent, rect),
+            None => rect,
+        };
+
+        self.clip_stack.push(clipped);
+    }
+
+    fn pop_clip_rect(&mut self) {
+        self.flush_clip_batch();
+        self.clip_stack
+            .pop()
+            .expect("pop_clip_rect() called without a matching push_clip_rect()");
+    }
+
+    fn create_render_target(&mut self, size: Size2d) -> RenderTargetId {
+        let texture =
+            glium::texture::Texture2d::empty(&self.display, size.width(), size.height()).unwrap();
+        let matrix = screen_matrix(size);
+        self.render_targets
+            .push(RenderTargetData { texture, matrix });
+        self.render_targets.len() - 1
+    }
+
+    fn begin_target(&mut self, id: RenderTargetId) {
+        assert!(
+            self.active_target.is_none(),
+            "begin_target() called while another render target is already bound"
+        );
+        self.flush_clip_batch();
+
+        // Targets accumulate across frames otherwise: clear out whatever the
+        // previous begin_target()/draw_target() round left behind.
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(
+            &self.display,
+            &self.render_targets[id].texture,
+        )
+        .unwrap();
+        framebuffer.clear_color(0.0, 0.0, 0.0, 0.0);
+
+        self.active_target = Some(id);
+    }
+
+    fn end_target(&mut self) {
+        assert!(
+            self.active_target.is_some(),
+            "end_target() called without a matching begin_target()"
+        );
+        self.flush_clip_batch();
+        self.active_target = None;
+    }
+
+    fn draw_target(&mut self, id: RenderTargetId, position: Point, size: Point, z: i32) {
+        self.pending_composites.push(PendingComposite {
+            id,
+            position,
+            size,
+            z,
+            scissor: self.current_scissor(),
+        });
+    }
 }
 
 pub fn get_other_corners(position: (f32, f32), size: (f32, f32)) -> [(f32, f32); 3] {
@@ -0,0 +1,19 @@
+use glium::Program;
+use std::fs;
+
+/// Loads and compiles a GLSL program from two files in `resources/`.
+///
+/// # Panics
+///
+/// Panics if either file can't be read, or if the shader pair fails to compile.
+pub fn load_program(display: &glium::Display, vertex_file: &str, fragment_file: &str) -> Program {
+    let vertex_shader = read_resource(vertex_file);
+    let fragment_shader = read_resource(fragment_file);
+
+    Program::from_source(display, &vertex_shader, &fragment_shader, None).unwrap()
+}
+
+fn read_resource(filename: &str) -> String {
+    let path = format!("resources/{}", filename);
+    fs::read_to_string(&path).unwrap_or_else(|error| panic!("Could not load {}: {}", path, error))
+}